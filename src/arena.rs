@@ -0,0 +1,275 @@
+use std::collections::{HashSet, VecDeque};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct NodeIndex(usize);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct EdgeIndex(usize);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Direction {
+    Outgoing,
+    Incoming,
+}
+
+impl Direction {
+    fn slot(self) -> usize {
+        match self {
+            Direction::Outgoing => 0,
+            Direction::Incoming => 1,
+        }
+    }
+}
+
+struct NodeEntry<N> {
+    data: N,
+    // Head of the outgoing/incoming `next_edge` chain, indexed by `Direction::slot`.
+    first_edge: [Option<EdgeIndex>; 2],
+}
+
+struct EdgeEntry<E> {
+    source: NodeIndex,
+    target: NodeIndex,
+    data: E,
+    // Link to the next edge in `source`'s outgoing chain and `target`'s
+    // incoming chain, indexed by `Direction::slot`.
+    next_edge: [Option<EdgeIndex>; 2],
+}
+
+/// An arena-backed graph: nodes and edges live in flat `Vec`s indexed by
+/// `NodeIndex`/`EdgeIndex`, with per-node outgoing/incoming edge lists
+/// threaded through `next_edge` links in the edge arena. Unlike
+/// `node_rc::Node`/`node_ref::Node`, nodes here have no lifetime or
+/// `Rc`-aliasing constraints, so predecessors are as cheap to enumerate as
+/// successors and the underlying data (`N`/`E`) can be mutated in place.
+pub struct Graph<N, E> {
+    nodes: Vec<NodeEntry<N>>,
+    edges: Vec<EdgeEntry<E>>,
+}
+
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Graph<N, E> {
+        Graph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn new() -> Graph<N, E> {
+        Graph::default()
+    }
+
+    pub fn add_node(&mut self, data: N) -> NodeIndex {
+        let index = NodeIndex(self.nodes.len());
+        self.nodes.push(NodeEntry {
+            data,
+            first_edge: [None, None],
+        });
+        index
+    }
+
+    pub fn add_edge(&mut self, source: NodeIndex, target: NodeIndex, data: E) -> EdgeIndex {
+        let index = EdgeIndex(self.edges.len());
+        let next_edge = [
+            self.nodes[source.0].first_edge[Direction::Outgoing.slot()],
+            self.nodes[target.0].first_edge[Direction::Incoming.slot()],
+        ];
+
+        self.edges.push(EdgeEntry {
+            source,
+            target,
+            data,
+            next_edge,
+        });
+        self.nodes[source.0].first_edge[Direction::Outgoing.slot()] = Some(index);
+        self.nodes[target.0].first_edge[Direction::Incoming.slot()] = Some(index);
+
+        index
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn node(&self, index: NodeIndex) -> &N {
+        &self.nodes[index.0].data
+    }
+
+    pub fn node_mut(&mut self, index: NodeIndex) -> &mut N {
+        &mut self.nodes[index.0].data
+    }
+
+    pub fn edge(&self, index: EdgeIndex) -> &E {
+        &self.edges[index.0].data
+    }
+
+    pub fn edge_mut(&mut self, index: EdgeIndex) -> &mut E {
+        &mut self.edges[index.0].data
+    }
+
+    pub fn edge_endpoints(&self, index: EdgeIndex) -> (NodeIndex, NodeIndex) {
+        let edge = &self.edges[index.0];
+        (edge.source, edge.target)
+    }
+
+    /// Walks `node`'s outgoing or incoming edges by following the
+    /// `next_edge` chain threaded through the edge arena.
+    pub fn edges(&self, node: NodeIndex, direction: Direction) -> Edges<'_, N, E> {
+        Edges {
+            graph: self,
+            direction,
+            current: self.nodes[node.0].first_edge[direction.slot()],
+        }
+    }
+
+    pub fn dfs<F>(&self, start: NodeIndex, f: F)
+    where
+        F: FnMut(NodeIndex),
+    {
+        self.traverse(start, LifoFrontier::default(), f)
+    }
+
+    pub fn bfs<F>(&self, start: NodeIndex, f: F)
+    where
+        F: FnMut(NodeIndex),
+    {
+        self.traverse(start, FifoFrontier::default(), f)
+    }
+
+    fn traverse<F, Q>(&self, start: NodeIndex, mut to_visit: Q, mut f: F)
+    where
+        F: FnMut(NodeIndex),
+        Q: Frontier,
+    {
+        let mut already_visited: HashSet<NodeIndex> = HashSet::new();
+
+        to_visit.push(start);
+        already_visited.insert(start);
+
+        while let Some(current) = to_visit.pop() {
+            f(current);
+
+            for edge in self.edges(current, Direction::Outgoing) {
+                let (_, target) = self.edge_endpoints(edge);
+                if already_visited.insert(target) {
+                    to_visit.push(target);
+                }
+            }
+        }
+    }
+}
+
+trait Frontier {
+    fn push(&mut self, node: NodeIndex);
+    fn pop(&mut self) -> Option<NodeIndex>;
+}
+
+#[derive(Default)]
+struct FifoFrontier {
+    data: VecDeque<NodeIndex>,
+}
+
+impl Frontier for FifoFrontier {
+    fn push(&mut self, node: NodeIndex) {
+        self.data.push_back(node)
+    }
+
+    fn pop(&mut self) -> Option<NodeIndex> {
+        self.data.pop_front()
+    }
+}
+
+#[derive(Default)]
+struct LifoFrontier {
+    data: VecDeque<NodeIndex>,
+}
+
+impl Frontier for LifoFrontier {
+    fn push(&mut self, node: NodeIndex) {
+        self.data.push_back(node)
+    }
+
+    fn pop(&mut self) -> Option<NodeIndex> {
+        self.data.pop_back()
+    }
+}
+
+pub struct Edges<'a, N, E> {
+    graph: &'a Graph<N, E>,
+    direction: Direction,
+    current: Option<EdgeIndex>,
+}
+
+impl<N, E> Iterator for Edges<'_, N, E> {
+    type Item = EdgeIndex;
+
+    fn next(&mut self) -> Option<EdgeIndex> {
+        let edge_index = self.current?;
+        self.current = self.graph.edges[edge_index.0].next_edge[self.direction.slot()];
+        Some(edge_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bfs_and_dfs_visit_every_reachable_node() {
+        let mut graph: Graph<&str, u32> = Graph::new();
+        let n1 = graph.add_node("n1");
+        let n2 = graph.add_node("n2");
+        let n3 = graph.add_node("n3");
+        graph.add_edge(n1, n2, 1);
+        graph.add_edge(n1, n3, 1);
+        graph.add_edge(n2, n3, 1);
+
+        // Each new edge is threaded onto the head of its source's outgoing
+        // list, so `n1`'s edges are walked most-recently-added first.
+        let mut bfs_order = Vec::new();
+        graph.bfs(n1, |node| bfs_order.push(*graph.node(node)));
+        assert_eq!(bfs_order, vec!["n1", "n3", "n2"]);
+
+        let mut dfs_visited = HashSet::new();
+        graph.dfs(n1, |node| {
+            dfs_visited.insert(*graph.node(node));
+        });
+        assert_eq!(dfs_visited, HashSet::from(["n1", "n2", "n3"]));
+    }
+
+    #[test]
+    fn outgoing_and_incoming_edges_are_both_cheaply_enumerable() {
+        let mut graph: Graph<&str, u32> = Graph::new();
+        let n1 = graph.add_node("n1");
+        let n2 = graph.add_node("n2");
+        let n3 = graph.add_node("n3");
+        graph.add_edge(n1, n3, 10);
+        graph.add_edge(n2, n3, 20);
+
+        let incoming: Vec<u32> = graph
+            .edges(n3, Direction::Incoming)
+            .map(|edge| *graph.edge(edge))
+            .collect();
+        assert_eq!(incoming.len(), 2);
+        assert!(incoming.contains(&10));
+        assert!(incoming.contains(&20));
+
+        let outgoing: Vec<NodeIndex> = graph
+            .edges(n1, Direction::Outgoing)
+            .map(|edge| graph.edge_endpoints(edge).1)
+            .collect();
+        assert_eq!(outgoing, vec![n3]);
+    }
+
+    #[test]
+    fn node_mut_allows_in_place_mutation() {
+        let mut graph: Graph<u32, ()> = Graph::new();
+        let n1 = graph.add_node(1);
+
+        *graph.node_mut(n1) += 41;
+
+        assert_eq!(*graph.node(n1), 42);
+    }
+}
@@ -1,4 +1,6 @@
-use std::collections::{HashSet, VecDeque};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::rc::Rc;
 use std::slice::Iter;
 
@@ -69,9 +71,598 @@ impl<'a> NodeQueue<'a> for LifoNodeQueue<'a> {
     }
 }
 
-struct Graph {}
+// Priority-ordered by `priority` only (ascending), so a plain `BinaryHeap`
+// (a max-heap) behaves as a min-heap when ordered by `Reverse`-style comparison.
+#[derive(Debug, Clone, Copy)]
+struct HeapEntry<'a> {
+    priority: u32,
+    distance: u32,
+    node: &'a Node,
+}
+
+impl PartialEq for HeapEntry<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl Eq for HeapEntry<'_> {}
+
+impl PartialOrd for HeapEntry<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+// A d-ary max-heap with the same push/pop shape as `BinaryHeap`, so it can be
+// dropped in wherever a larger branching factor would cut decrease-key churn
+// on dense frontiers.
+struct DAryHeap<T: Ord> {
+    arity: usize,
+    data: Vec<T>,
+}
+
+impl<T: Ord> DAryHeap<T> {
+    fn new(arity: usize) -> DAryHeap<T> {
+        DAryHeap {
+            arity: arity.max(2),
+            data: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, item: T) {
+        self.data.push(item);
+        self.sift_up(self.data.len() - 1);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let last = self.data.len() - 1;
+        self.data.swap(0, last);
+        let item = self.data.pop();
+        if !self.data.is_empty() {
+            self.sift_down(0);
+        }
+        item
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / self.arity;
+            if self.data[parent] < self.data[i] {
+                self.data.swap(i, parent);
+                i = parent;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let first_child = i * self.arity + 1;
+            if first_child >= self.data.len() {
+                break;
+            }
+            let last_child = (first_child + self.arity).min(self.data.len());
+            let largest = (first_child..last_child)
+                .max_by(|&a, &b| self.data[a].cmp(&self.data[b]))
+                .unwrap();
+            if self.data[i] < self.data[largest] {
+                self.data.swap(i, largest);
+                i = largest;
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+/// How a DFS edge relates the discovery/finish intervals of its endpoints.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EdgeKind {
+    /// `child` was first discovered through this edge.
+    Tree,
+    /// `child` is an ancestor still on the active DFS stack (a cycle).
+    Back,
+    /// `child` is a already-finished descendant reached via a shortcut.
+    Forward,
+    /// `child` is already-finished and not a descendant of `node`.
+    Cross,
+}
+
+#[derive(Default)]
+struct Graph {
+    nodes: Vec<Rc<Node>>,
+}
 
 impl Graph {
+    pub fn new() -> Graph {
+        Graph::default()
+    }
+
+    /// The nodes owned by this graph; for a graph built from an adjacency
+    /// matrix, `nodes()[i]` is the node of row `i`.
+    pub fn nodes(&self) -> &[Rc<Node>] {
+        &self.nodes
+    }
+
+    /// Builds an (unweighted) graph from a whitespace-separated 0/1
+    /// adjacency matrix: a nonzero cell at row `i`, column `j` becomes an
+    /// edge `i -> j` of weight 1. Row index is the node id.
+    ///
+    /// `Node`'s children are fixed at construction, so the matrix must
+    /// describe a DAG: this builds nodes in an order where every edge's
+    /// destination already exists before its source does. Returns `Err` on
+    /// a malformed cell or a cycle, rather than panicking on caller input.
+    pub fn from_adjacency_matrix(text: &str) -> Result<Graph, String> {
+        Self::build_from_matrix(&Self::parse_matrix(text)?, false)
+    }
+
+    /// Same as `from_adjacency_matrix`, but each nonzero cell's value
+    /// becomes the edge's `weight` instead of being collapsed to `1`.
+    pub fn from_weighted_adjacency_matrix(text: &str) -> Result<Graph, String> {
+        Self::build_from_matrix(&Self::parse_matrix(text)?, true)
+    }
+
+    /// Renders this graph back into the text format `from_adjacency_matrix`
+    /// and `from_weighted_adjacency_matrix` accept, for round-tripping and
+    /// test fixtures. Rows are keyed on each node's position in `self.nodes`
+    /// rather than its `id`, so this doesn't require dense `0..n` ids.
+    pub fn to_adjacency_matrix(&self) -> String {
+        let n = self.nodes.len();
+        let index_of: HashMap<u32, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id, i))
+            .collect();
+        let mut rows = vec![vec![0u32; n]; n];
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            for edge in node.children_edges_iter() {
+                let j = index_of[&edge.destination_node.id];
+                rows[i][j] = edge.weight;
+            }
+        }
+
+        rows.iter()
+            .map(|row| {
+                row.iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn parse_matrix(text: &str) -> Result<Vec<Vec<u32>>, String> {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|cell| {
+                        cell.parse::<u32>()
+                            .map_err(|_| format!("adjacency matrix cell {cell:?} is not a non-negative integer"))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn build_from_matrix(matrix: &[Vec<u32>], weighted: bool) -> Result<Graph, String> {
+        let n = matrix.len();
+        if let Some(row) = matrix.iter().find(|row| row.len() != n) {
+            return Err(format!(
+                "adjacency matrix is not square: a row has {} cells, expected {n}",
+                row.len()
+            ));
+        }
+        let order = Self::build_order(matrix, n)?;
+
+        let mut built: HashMap<usize, Rc<Node>> = HashMap::with_capacity(n);
+        for &i in &order {
+            let mut children = Vec::new();
+            for (j, &cell) in matrix[i].iter().enumerate() {
+                if cell != 0 {
+                    let weight = if weighted { cell } else { 1 };
+                    let destination = built
+                        .get(&j)
+                        .expect("build_order guarantees every destination is already built")
+                        .clone();
+                    children.push(Edge::new(weight, destination));
+                }
+            }
+            built.insert(i, Rc::new(Node::new(i as u32, children)));
+        }
+
+        let nodes = (0..n).map(|i| built.remove(&i).unwrap()).collect();
+        Ok(Graph { nodes })
+    }
+
+    /// An order in which `build_from_matrix` can construct nodes so that
+    /// every edge's destination is already built: a topological sort of the
+    /// "depends on" graph where `i` depends on `j` for every edge `i -> j`.
+    /// Returns `Err` if the matrix isn't a DAG, since `Node`'s children are
+    /// fixed at construction and can't represent a cycle.
+    fn build_order(matrix: &[Vec<u32>], n: usize) -> Result<Vec<usize>, String> {
+        let mut in_degree = vec![0u32; n];
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); n];
+
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, &cell) in row.iter().enumerate() {
+                if cell != 0 {
+                    in_degree[i] += 1;
+                    dependents[j].push(i);
+                }
+            }
+        }
+
+        let mut queue: VecDeque<usize> = (0..n).filter(|&i| in_degree[i] == 0).collect();
+        let mut order = Vec::with_capacity(n);
+
+        while let Some(j) = queue.pop_front() {
+            order.push(j);
+            for &i in &dependents[j] {
+                in_degree[i] -= 1;
+                if in_degree[i] == 0 {
+                    queue.push_back(i);
+                }
+            }
+        }
+
+        if order.len() != n {
+            return Err(
+                "adjacency matrix contains a cycle; Node's children are fixed at \
+                 construction, so only DAGs can be built today"
+                    .to_string(),
+            );
+        }
+
+        Ok(order)
+    }
+
+    /// Transitive closure of this graph's edges, computed by sweeping every
+    /// edge until a sweep changes no row. One sweep costs O(E·V/64); the
+    /// number of sweeps grows with the longest path, so the whole precompute
+    /// is not bounded by a single sweep. Afterwards,
+    /// `ReachabilityMatrix::can_reach` answers reachability in O(1) by
+    /// testing a single packed bit.
+    pub fn reachability(&self) -> ReachabilityMatrix {
+        let n = self.nodes.len();
+        let index_of: HashMap<u32, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id, i))
+            .collect();
+        let mut bits = BitMatrix::new(n);
+
+        // Seed each row with its direct successors.
+        for (i, node) in self.nodes.iter().enumerate() {
+            for edge in node.children_edges_iter() {
+                bits.insert(i, index_of[&edge.destination_node.id]);
+            }
+        }
+
+        // Fixed point: OR a node's row into each of its predecessors' rows
+        // until nothing changes, same as a dataflow bit-vector union.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (i, node) in self.nodes.iter().enumerate() {
+                for edge in node.children_edges_iter() {
+                    let j = index_of[&edge.destination_node.id];
+                    if bits.union_from(i, j) {
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        ReachabilityMatrix { index_of, bits }
+    }
+
+    /// Tarjan's algorithm: strongly connected components reachable from
+    /// `start`, each returned as its member node ids. Uses an explicit stack
+    /// of `(node, remaining children)` frames instead of recursion, and keys
+    /// all bookkeeping on `Node::id` since `Rc<Node>` equality is whole-struct.
+    pub fn strongly_connected_components(&self, start: &Node) -> Vec<Vec<u32>> {
+        let mut index: HashMap<u32, u32> = HashMap::new();
+        let mut lowlink: HashMap<u32, u32> = HashMap::new();
+        let mut on_stack: HashSet<u32> = HashSet::new();
+        let mut tarjan_stack: Vec<&Node> = Vec::new();
+        let mut next_index = 0;
+        let mut sccs: Vec<Vec<u32>> = Vec::new();
+
+        // Simulated call stack: the node being explored and its remaining children.
+        let mut work: Vec<(&Node, Iter<'_, Edge>)> = Vec::new();
+
+        index.insert(start.id, next_index);
+        lowlink.insert(start.id, next_index);
+        next_index += 1;
+        on_stack.insert(start.id);
+        tarjan_stack.push(start);
+        work.push((start, start.children_edges_iter()));
+
+        while let Some((node, mut children)) = work.pop() {
+            if let Some(edge) = children.next() {
+                let child = edge.destination_node.as_ref();
+                work.push((node, children));
+
+                if !index.contains_key(&child.id) {
+                    index.insert(child.id, next_index);
+                    lowlink.insert(child.id, next_index);
+                    next_index += 1;
+                    on_stack.insert(child.id);
+                    tarjan_stack.push(child);
+                    work.push((child, child.children_edges_iter()));
+                } else if on_stack.contains(&child.id) {
+                    // Back edge to a node still on the stack.
+                    let candidate = index[&child.id];
+                    let current = lowlink[&node.id];
+                    lowlink.insert(node.id, current.min(candidate));
+                }
+            } else {
+                // `node` has no more children: it is finished.
+                if lowlink[&node.id] == index[&node.id] {
+                    let mut component = Vec::new();
+                    while let Some(member) = tarjan_stack.pop() {
+                        on_stack.remove(&member.id);
+                        component.push(member.id);
+                        if member.id == node.id {
+                            break;
+                        }
+                    }
+                    sccs.push(component);
+                }
+
+                // Propagate the now-final lowlink up to the parent frame.
+                if let Some((parent, _)) = work.last() {
+                    let parent_low = lowlink[&parent.id];
+                    let node_low = lowlink[&node.id];
+                    lowlink.insert(parent.id, parent_low.min(node_low));
+                }
+            }
+        }
+
+        sccs
+    }
+
+    /// Classifies every edge a DFS from `start` traverses as `Tree`, `Back`,
+    /// `Forward` or `Cross`, using discovery timestamps and an on-stack set
+    /// (white = undiscovered, gray = on stack, black = discovered and off
+    /// the stack) per node, keyed by `Node::id`.
+    pub fn classify_edges(&self, start: &Node) -> Vec<(u32, u32, EdgeKind)> {
+        let mut discovery: HashMap<u32, u32> = HashMap::new();
+        let mut on_stack: HashSet<u32> = HashSet::new();
+        let mut clock = 0;
+        let mut edges = Vec::new();
+
+        self.classify_edges_visit(start, &mut discovery, &mut on_stack, &mut clock, &mut edges);
+
+        edges
+    }
+
+    fn classify_edges_visit(
+        &self,
+        node: &Node,
+        discovery: &mut HashMap<u32, u32>,
+        on_stack: &mut HashSet<u32>,
+        clock: &mut u32,
+        edges: &mut Vec<(u32, u32, EdgeKind)>,
+    ) {
+        discovery.insert(node.id, *clock);
+        *clock += 1;
+        on_stack.insert(node.id);
+
+        for edge in node.children_edges_iter() {
+            let child = edge.destination_node.as_ref();
+
+            let kind = if !discovery.contains_key(&child.id) {
+                // White: unvisited.
+                edges.push((node.id, child.id, EdgeKind::Tree));
+                self.classify_edges_visit(child, discovery, on_stack, clock, edges);
+                continue;
+            } else if on_stack.contains(&child.id) {
+                // Gray: still an active ancestor.
+                EdgeKind::Back
+            } else if discovery[&node.id] < discovery[&child.id] {
+                // Black, discovered after `node`: a descendant reached by a shortcut.
+                EdgeKind::Forward
+            } else {
+                // Black, discovered before `node`: unrelated subtree.
+                EdgeKind::Cross
+            };
+
+            edges.push((node.id, child.id, kind));
+        }
+
+        on_stack.remove(&node.id);
+    }
+
+    /// Minimum total-weight distance from `start` to every reachable node,
+    /// stopping early if `target` is given and popped off the frontier.
+    pub fn dijkstra(&self, start: &Node, target: Option<&Node>) -> HashMap<u32, u32> {
+        self.dijkstra_internal(start, target, |_| 0, 2).0
+    }
+
+    /// Same as `dijkstra`, but also reconstructs the shortest path to `target`.
+    pub fn dijkstra_path(&self, start: &Node, target: &Node) -> Option<Vec<u32>> {
+        let (dist, prev) = self.dijkstra_internal(start, Some(target), |_| 0, 2);
+        Self::reconstruct_path(start.id, target.id, &dist, &prev)
+    }
+
+    /// Dijkstra ordered by `distance + h(node)` instead of `distance` alone.
+    /// `h` must be admissible (never overestimate the true remaining cost)
+    /// for the result to be optimal.
+    pub fn astar<H>(&self, start: &Node, target: &Node, h: H) -> HashMap<u32, u32>
+    where
+        H: Fn(&Node) -> u32,
+    {
+        self.dijkstra_internal(start, Some(target), h, 2).0
+    }
+
+    pub fn astar_path<H>(&self, start: &Node, target: &Node, h: H) -> Option<Vec<u32>>
+    where
+        H: Fn(&Node) -> u32,
+    {
+        let (dist, prev) = self.dijkstra_internal(start, Some(target), h, 2);
+        Self::reconstruct_path(start.id, target.id, &dist, &prev)
+    }
+
+    /// Same as `dijkstra`, but lets the caller pick the heap's branching
+    /// factor. A higher `arity` trades slower `pop` for cheaper `push`,
+    /// which pays off when relaxations vastly outnumber pops.
+    pub fn dijkstra_with_arity(
+        &self,
+        start: &Node,
+        target: Option<&Node>,
+        arity: usize,
+    ) -> HashMap<u32, u32> {
+        self.dijkstra_internal(start, target, |_| 0, arity).0
+    }
+
+    fn dijkstra_internal<H>(
+        &self,
+        start: &Node,
+        target: Option<&Node>,
+        h: H,
+        arity: usize,
+    ) -> (HashMap<u32, u32>, HashMap<u32, u32>)
+    where
+        H: Fn(&Node) -> u32,
+    {
+        let mut dist: HashMap<u32, u32> = HashMap::new();
+        let mut prev: HashMap<u32, u32> = HashMap::new();
+        let mut frontier = DAryHeap::new(arity);
+
+        dist.insert(start.id, 0);
+        frontier.push(HeapEntry {
+            priority: h(start),
+            distance: 0,
+            node: start,
+        });
+
+        while let Some(current) = frontier.pop() {
+            // Stale entry: a cheaper path to this node was already finalized.
+            if current.distance > *dist.get(&current.node.id).unwrap_or(&u32::MAX) {
+                continue;
+            }
+
+            if let Some(target) = target {
+                if current.node.id == target.id {
+                    break;
+                }
+            }
+
+            for edge in current.node.children_edges_iter() {
+                let child = edge.destination_node.as_ref();
+                let candidate = current.distance.saturating_add(edge.weight);
+
+                if candidate < *dist.get(&child.id).unwrap_or(&u32::MAX) {
+                    dist.insert(child.id, candidate);
+                    prev.insert(child.id, current.node.id);
+                    frontier.push(HeapEntry {
+                        priority: candidate.saturating_add(h(child)),
+                        distance: candidate,
+                        node: child,
+                    });
+                }
+            }
+        }
+
+        (dist, prev)
+    }
+
+    fn reconstruct_path(
+        start_id: u32,
+        target_id: u32,
+        dist: &HashMap<u32, u32>,
+        prev: &HashMap<u32, u32>,
+    ) -> Option<Vec<u32>> {
+        dist.get(&target_id)?;
+
+        let mut path = vec![target_id];
+        let mut current = target_id;
+        while current != start_id {
+            current = *prev.get(&current)?;
+            path.push(current);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Whether every node in `self.nodes` that touches at least one edge is
+    /// reachable from `start`, ignoring isolated (degree-zero) vertices.
+    /// When `self.nodes` is empty (e.g. a graph built directly from `Node`s
+    /// rather than `from_adjacency_matrix`), there is no registry to check
+    /// against, so the traversal from `start` is trusted as covering the
+    /// whole graph.
+    pub fn is_connected(&self, start: &Node) -> bool {
+        let mut touches_edge: HashSet<u32> = HashSet::new();
+        for node in &self.nodes {
+            for edge in node.children_edges_iter() {
+                touches_edge.insert(node.id);
+                touches_edge.insert(edge.destination_node.as_ref().id);
+            }
+        }
+
+        let reached: RefCell<HashSet<u32>> = RefCell::new(HashSet::new());
+
+        self.bfs(start, |node| {
+            reached.borrow_mut().insert(node.id);
+        });
+
+        let reached = reached.into_inner();
+        touches_edge.iter().all(|id| reached.contains(id))
+    }
+
+    /// An Euler trail exists from `start`'s connected component iff it is
+    /// connected and has exactly zero or two odd-degree vertices. Each stored
+    /// edge is treated as contributing to both endpoints' degree, with the
+    /// reverse edge (if present) deduplicated so an undirected edge is only
+    /// counted once.
+    pub fn has_euler_path(&self, start: &Node) -> bool {
+        if !self.is_connected(start) {
+            return false;
+        }
+
+        let degree: RefCell<HashMap<u32, u32>> = RefCell::new(HashMap::new());
+        let counted: RefCell<HashSet<(u32, u32)>> = RefCell::new(HashSet::new());
+
+        self.bfs(start, |node| {
+            for edge in node.children_edges_iter() {
+                let child = edge.destination_node.as_ref();
+
+                if counted.borrow().contains(&(child.id, node.id)) {
+                    continue;
+                }
+                counted.borrow_mut().insert((node.id, child.id));
+
+                let mut degree = degree.borrow_mut();
+                *degree.entry(node.id).or_insert(0) += 1;
+                *degree.entry(child.id).or_insert(0) += 1;
+            }
+        });
+
+        let odd_degree_count = degree
+            .into_inner()
+            .values()
+            .filter(|&&d| d % 2 == 1)
+            .count();
+        odd_degree_count == 0 || odd_degree_count == 2
+    }
+
     pub fn dfs<F>(&self, start: &Node, f: F)
     where
         F: Fn(&Node),
@@ -109,6 +700,365 @@ impl Graph {
     }
 }
 
+/// A packed bit matrix, one row per source and one bit per target, usable
+/// for reachability or any other node-indexed set relation.
+pub struct BitMatrix {
+    rows: Vec<Vec<u64>>,
+}
+
+impl BitMatrix {
+    pub fn new(len: usize) -> BitMatrix {
+        let words_per_row = len.div_ceil(64).max(1);
+        BitMatrix {
+            rows: vec![vec![0u64; words_per_row]; len],
+        }
+    }
+
+    /// Sets the `target` bit in `source`'s row. Returns whether the bit
+    /// flipped from 0 to 1.
+    pub fn insert(&mut self, source: usize, target: usize) -> bool {
+        let (word, mask) = (target / 64, 1u64 << (target % 64));
+        let changed = self.rows[source][word] & mask == 0;
+        self.rows[source][word] |= mask;
+        changed
+    }
+
+    pub fn contains(&self, source: usize, target: usize) -> bool {
+        let (word, mask) = (target / 64, 1u64 << (target % 64));
+        self.rows[source][word] & mask != 0
+    }
+
+    /// ORs `source`'s row into `target`'s row. Returns whether anything changed.
+    pub fn union_from(&mut self, target: usize, source: usize) -> bool {
+        if target == source {
+            return false;
+        }
+
+        let (lo, hi) = (target.min(source), target.max(source));
+        let (head, tail) = self.rows.split_at_mut(hi);
+        let (target_row, source_row) = if target < source {
+            (&mut head[lo], &tail[0])
+        } else {
+            (&mut tail[0], &head[lo])
+        };
+
+        let mut changed = false;
+        for (word, &other) in target_row.iter_mut().zip(source_row.iter()) {
+            let merged = *word | other;
+            if merged != *word {
+                changed = true;
+                *word = merged;
+            }
+        }
+        changed
+    }
+}
+
+/// The transitive closure of a graph's edges, keyed by `Node::id` rather
+/// than the dense row index `BitMatrix` expects.
+pub struct ReachabilityMatrix {
+    index_of: HashMap<u32, usize>,
+    bits: BitMatrix,
+}
+
+impl ReachabilityMatrix {
+    pub fn can_reach(&self, source_id: u32, target_id: u32) -> bool {
+        let (Some(&source), Some(&target)) =
+            (self.index_of.get(&source_id), self.index_of.get(&target_id))
+        else {
+            return false;
+        };
+        self.bits.contains(source, target)
+    }
+}
+
+/// Iterative, array-backed segment tree over a fixed-size range, supporting
+/// point updates and O(log n) associative range folds with a caller-supplied
+/// `combine`.
+pub struct SegmentTree<T, F>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+{
+    tree: Vec<T>,
+    // Mirrors `tree`, but built over the values in reverse order, so a
+    // range can be folded back-to-front in O(log n) for non-commutative
+    // `combine` (used by `HeavyLightDecomposition::path_query` to read a
+    // chain in root-to-leaf or leaf-to-root order as the path requires).
+    reverse_tree: Vec<T>,
+    len: usize,
+    combine: F,
+    identity: T,
+}
+
+impl<T, F> SegmentTree<T, F>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+{
+    pub fn build(values: Vec<T>, combine: F, identity: T) -> SegmentTree<T, F> {
+        let len = values.len();
+        let mut tree = vec![identity.clone(); 2 * len.max(1)];
+        let mut reverse_tree = vec![identity.clone(); 2 * len.max(1)];
+
+        for (i, value) in values.iter().enumerate() {
+            tree[len + i] = value.clone();
+            reverse_tree[len + (len - 1 - i)] = value.clone();
+        }
+        for i in (1..len).rev() {
+            tree[i] = combine(&tree[2 * i], &tree[2 * i + 1]);
+            reverse_tree[i] = combine(&reverse_tree[2 * i], &reverse_tree[2 * i + 1]);
+        }
+
+        SegmentTree {
+            tree,
+            reverse_tree,
+            len,
+            combine,
+            identity,
+        }
+    }
+
+    pub fn update(&mut self, pos: usize, value: T) {
+        let mut i = pos + self.len;
+        self.tree[i] = value.clone();
+        i /= 2;
+        while i >= 1 {
+            self.tree[i] = (self.combine)(&self.tree[2 * i], &self.tree[2 * i + 1]);
+            i /= 2;
+        }
+
+        let mut j = (self.len - 1 - pos) + self.len;
+        self.reverse_tree[j] = value;
+        j /= 2;
+        while j >= 1 {
+            self.reverse_tree[j] = (self.combine)(&self.reverse_tree[2 * j], &self.reverse_tree[2 * j + 1]);
+            j /= 2;
+        }
+    }
+
+    /// Folds the inclusive range `[lo, hi]`, in left-to-right order, so
+    /// `combine` may be non-commutative (e.g. matrix product).
+    pub fn range_query(&self, lo: usize, hi: usize) -> T {
+        Self::fold(&self.tree, self.len, &self.combine, &self.identity, lo, hi)
+    }
+
+    /// Folds the inclusive range `[lo, hi]` back-to-front, i.e. `tree[hi]`
+    /// is combined first. Needed by callers (like `path_query`) that must
+    /// read a stored range in the reverse of its storage order.
+    fn reverse_range_query(&self, lo: usize, hi: usize) -> T {
+        let (rlo, rhi) = (self.len - 1 - hi, self.len - 1 - lo);
+        Self::fold(&self.reverse_tree, self.len, &self.combine, &self.identity, rlo, rhi)
+    }
+
+    fn fold(tree: &[T], len: usize, combine: &F, identity: &T, lo: usize, hi: usize) -> T {
+        let (mut lo, mut hi) = (lo + len, hi + len + 1);
+        let mut acc_left = identity.clone();
+        let mut acc_right = identity.clone();
+
+        while lo < hi {
+            if lo % 2 == 1 {
+                acc_left = combine(&acc_left, &tree[lo]);
+                lo += 1;
+            }
+            if hi % 2 == 1 {
+                hi -= 1;
+                acc_right = combine(&tree[hi], &acc_right);
+            }
+            lo /= 2;
+            hi /= 2;
+        }
+
+        combine(&acc_left, &acc_right)
+    }
+}
+
+/// Heavy-Light Decomposition of a tree rooted at a given node: precomputes
+/// each node's `parent`, `depth`, `chain_head` and a contiguous heavy-path
+/// `position`, so `path_query` can answer associative aggregate queries
+/// (sum, min, matrix product, ...) on the path from `u` to `v` in
+/// O(log^2 n), in path order, via an external `SegmentTree` built over
+/// nodes in `position` order.
+pub struct HeavyLightDecomposition {
+    parent: HashMap<u32, u32>,
+    depth: HashMap<u32, u32>,
+    chain_head: HashMap<u32, u32>,
+    position: HashMap<u32, usize>,
+    len: usize,
+}
+
+impl HeavyLightDecomposition {
+    /// Builds the decomposition via two DFS passes rooted at `root`: one to
+    /// compute subtree sizes and each node's heavy child (the child with the
+    /// largest subtree), and one to assign `chain_head`s and `position`s in
+    /// heavy-path order.
+    pub fn build(root: &Node) -> HeavyLightDecomposition {
+        let mut parent = HashMap::new();
+        let mut depth = HashMap::new();
+        let mut subtree_size = HashMap::new();
+        let mut heavy_child = HashMap::new();
+
+        Self::size_pass(
+            root,
+            None,
+            0,
+            &mut parent,
+            &mut depth,
+            &mut subtree_size,
+            &mut heavy_child,
+        );
+
+        let mut chain_head = HashMap::new();
+        let mut position = HashMap::new();
+        let mut next_position = 0;
+
+        Self::decompose_pass(
+            root,
+            root.id,
+            &heavy_child,
+            &mut chain_head,
+            &mut position,
+            &mut next_position,
+        );
+
+        HeavyLightDecomposition {
+            parent,
+            depth,
+            chain_head,
+            position,
+            len: next_position,
+        }
+    }
+
+    fn size_pass(
+        node: &Node,
+        parent_id: Option<u32>,
+        node_depth: u32,
+        parent: &mut HashMap<u32, u32>,
+        depth: &mut HashMap<u32, u32>,
+        subtree_size: &mut HashMap<u32, u32>,
+        heavy_child: &mut HashMap<u32, u32>,
+    ) -> u32 {
+        if let Some(parent_id) = parent_id {
+            parent.insert(node.id, parent_id);
+        }
+        depth.insert(node.id, node_depth);
+
+        let mut size = 1;
+        let mut heaviest: Option<(u32, u32)> = None;
+
+        for edge in node.children_edges_iter() {
+            let child = edge.destination_node.as_ref();
+            let child_size = Self::size_pass(
+                child,
+                Some(node.id),
+                node_depth + 1,
+                parent,
+                depth,
+                subtree_size,
+                heavy_child,
+            );
+            size += child_size;
+
+            if heaviest.is_none_or(|(_, heaviest_size)| child_size > heaviest_size) {
+                heaviest = Some((child.id, child_size));
+            }
+        }
+
+        subtree_size.insert(node.id, size);
+        if let Some((heavy_id, _)) = heaviest {
+            heavy_child.insert(node.id, heavy_id);
+        }
+
+        size
+    }
+
+    fn decompose_pass(
+        node: &Node,
+        head: u32,
+        heavy_child: &HashMap<u32, u32>,
+        chain_head: &mut HashMap<u32, u32>,
+        position: &mut HashMap<u32, usize>,
+        next_position: &mut usize,
+    ) {
+        chain_head.insert(node.id, head);
+        position.insert(node.id, *next_position);
+        *next_position += 1;
+
+        let heavy_id = heavy_child.get(&node.id).copied();
+
+        // Visit the heavy child first so its subtree stays contiguous in `position`.
+        if let Some(heavy_id) = heavy_id {
+            for edge in node.children_edges_iter() {
+                let child = edge.destination_node.as_ref();
+                if child.id == heavy_id {
+                    Self::decompose_pass(child, head, heavy_child, chain_head, position, next_position);
+                }
+            }
+        }
+
+        for edge in node.children_edges_iter() {
+            let child = edge.destination_node.as_ref();
+            if Some(child.id) != heavy_id {
+                Self::decompose_pass(child, child.id, heavy_child, chain_head, position, next_position);
+            }
+        }
+    }
+
+    /// The contiguous heavy-path-order index of `node_id`, i.e. its row in a
+    /// `SegmentTree` built over this decomposition.
+    pub fn position(&self, node_id: u32) -> usize {
+        self.position[&node_id]
+    }
+
+    /// The number of nodes covered by this decomposition.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Folds `segment_tree` over the path from `u` to `v` (in that order,
+    /// so a non-commutative `combine` such as matrix product is supported),
+    /// walking whichever endpoint's chain is deeper one chain at a time.
+    /// `acc_u` accumulates the `u -> LCA` portion (read deep-to-shallow,
+    /// i.e. back-to-front within each chain, and appended to as the walk
+    /// nears the LCA); `acc_v` accumulates the `LCA -> v` portion (read
+    /// shallow-to-deep, and prepended to for the same reason). The two are
+    /// joined once both endpoints share a chain.
+    pub fn path_query<T, F>(&self, mut u: u32, mut v: u32, segment_tree: &SegmentTree<T, F>) -> T
+    where
+        T: Clone,
+        F: Fn(&T, &T) -> T,
+    {
+        let mut acc_u = segment_tree.identity.clone();
+        let mut acc_v = segment_tree.identity.clone();
+
+        while self.chain_head[&u] != self.chain_head[&v] {
+            if self.depth[&self.chain_head[&u]] >= self.depth[&self.chain_head[&v]] {
+                let head = self.chain_head[&u];
+                let segment = segment_tree.reverse_range_query(self.position[&head], self.position[&u]);
+                acc_u = (segment_tree.combine)(&acc_u, &segment);
+                u = self.parent[&head];
+            } else {
+                let head = self.chain_head[&v];
+                let segment = segment_tree.range_query(self.position[&head], self.position[&v]);
+                acc_v = (segment_tree.combine)(&segment, &acc_v);
+                v = self.parent[&head];
+            }
+        }
+
+        let (pu, pv) = (self.position[&u], self.position[&v]);
+        if pu >= pv {
+            let segment = segment_tree.reverse_range_query(pv, pu);
+            acc_u = (segment_tree.combine)(&acc_u, &segment);
+        } else {
+            let segment = segment_tree.range_query(pu, pv);
+            acc_v = (segment_tree.combine)(&segment, &acc_v);
+        }
+
+        (segment_tree.combine)(&acc_u, &acc_v)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,7 +1080,7 @@ mod tests {
             vec![Edge::new(1, n2.clone()), Edge::new(1, n3.clone())],
         ));
 
-        let graph = Graph {};
+        let graph = Graph::new();
 
         println!("DFS");
         graph.dfs(&n1, |node| println!("Visited {:?}", node));
@@ -138,4 +1088,242 @@ mod tests {
         println!("BFS");
         graph.bfs(&n1, |node| println!("Visited {:?}", node));
     }
+
+    #[test]
+    fn dijkstra_finds_minimum_weight_distances() {
+        let n4 = Rc::new(Node::new(4, vec![]));
+        let n3 = Rc::new(Node::new(3, vec![Edge::new(1, n4.clone())]));
+        let n2 = Rc::new(Node::new(2, vec![Edge::new(5, n4.clone())]));
+        let n1 = Rc::new(Node::new(
+            1,
+            vec![Edge::new(2, n2.clone()), Edge::new(1, n3.clone())],
+        ));
+
+        let graph = Graph::new();
+        let dist = graph.dijkstra(&n1, None);
+
+        assert_eq!(dist[&1], 0);
+        assert_eq!(dist[&2], 2);
+        assert_eq!(dist[&3], 1);
+        // Via n3 (1 + 1 = 2) is cheaper than the direct edge through n2 (2 + 5 = 7).
+        assert_eq!(dist[&4], 2);
+
+        let path = graph.dijkstra_path(&n1, &n4).unwrap();
+        assert_eq!(path, vec![1, 3, 4]);
+    }
+
+    #[test]
+    fn astar_matches_dijkstra_with_zero_heuristic() {
+        let n3 = Rc::new(Node::new(3, vec![]));
+        let n2 = Rc::new(Node::new(2, vec![Edge::new(1, n3.clone())]));
+        let n1 = Rc::new(Node::new(1, vec![Edge::new(1, n2.clone())]));
+
+        let graph = Graph::new();
+        let path = graph.astar_path(&n1, &n3, |_| 0).unwrap();
+
+        assert_eq!(path, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn classify_edges_finds_tree_and_forward_edges() {
+        // n1 -> n2 -> n3, plus a shortcut n1 -> n3 explored after n3 is finished.
+        let n3 = Rc::new(Node::new(3, vec![]));
+        let n2 = Rc::new(Node::new(2, vec![Edge::new(1, n3.clone())]));
+        let n1 = Rc::new(Node::new(
+            1,
+            vec![Edge::new(1, n2.clone()), Edge::new(1, n3.clone())],
+        ));
+
+        let graph = Graph::new();
+        let edges = graph.classify_edges(&n1);
+
+        assert!(edges.contains(&(1, 2, EdgeKind::Tree)));
+        assert!(edges.contains(&(2, 3, EdgeKind::Tree)));
+        assert!(edges.contains(&(1, 3, EdgeKind::Forward)));
+    }
+
+    #[test]
+    fn scc_of_an_acyclic_graph_is_all_singletons() {
+        // `Rc<Node>` can only wire edges to already-built nodes, so every
+        // graph this crate can construct today is a DAG: each node is its
+        // own strongly connected component.
+        let n4 = Rc::new(Node::new(4, vec![]));
+        let n3 = Rc::new(Node::new(3, vec![Edge::new(1, n4.clone())]));
+        let n2 = Rc::new(Node::new(2, vec![Edge::new(1, n4.clone())]));
+        let n1 = Rc::new(Node::new(
+            1,
+            vec![Edge::new(1, n2.clone()), Edge::new(1, n3.clone())],
+        ));
+
+        let graph = Graph::new();
+        let sccs = graph.strongly_connected_components(&n1);
+
+        assert_eq!(sccs.len(), 4);
+        assert!(sccs.iter().all(|component| component.len() == 1));
+
+        let mut ids: Vec<u32> = sccs.into_iter().flatten().collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn hld_path_query_sums_values_along_a_tree_path() {
+        // n1 is the root, with n2 (which has child n4) and n3 as children.
+        let n4 = Rc::new(Node::new(4, vec![]));
+        let n3 = Rc::new(Node::new(3, vec![]));
+        let n2 = Rc::new(Node::new(2, vec![Edge::new(1, n4.clone())]));
+        let n1 = Rc::new(Node::new(
+            1,
+            vec![Edge::new(1, n2.clone()), Edge::new(1, n3.clone())],
+        ));
+
+        let hld = HeavyLightDecomposition::build(&n1);
+
+        let mut values = vec![0u32; hld.len()];
+        for id in [1, 2, 3, 4] {
+            values[hld.position(id)] = id;
+        }
+        let segment_tree = SegmentTree::build(values, |a: &u32, b: &u32| a + b, 0);
+
+        // Path n4 -> n2 -> n1 -> n3 sums values 4 + 2 + 1 + 3.
+        assert_eq!(hld.path_query(4, 3, &segment_tree), 10);
+        // A path from a node to itself is just its own value.
+        assert_eq!(hld.path_query(2, 2, &segment_tree), 2);
+    }
+
+    #[test]
+    fn hld_path_query_preserves_path_order_for_a_non_commutative_combine() {
+        // n1 is the root, with n2 (which has child n4) and n3 as children.
+        let n4 = Rc::new(Node::new(4, vec![]));
+        let n3 = Rc::new(Node::new(3, vec![]));
+        let n2 = Rc::new(Node::new(2, vec![Edge::new(1, n4.clone())]));
+        let n1 = Rc::new(Node::new(
+            1,
+            vec![Edge::new(1, n2.clone()), Edge::new(1, n3.clone())],
+        ));
+
+        let hld = HeavyLightDecomposition::build(&n1);
+
+        let letters = ["A", "B", "C", "D"];
+        let mut values = vec![String::new(); hld.len()];
+        for id in [1, 2, 3, 4] {
+            values[hld.position(id)] = letters[(id - 1) as usize].to_string();
+        }
+        let segment_tree =
+            SegmentTree::build(values, |a: &String, b: &String| format!("{a}{b}"), String::new());
+
+        // Path n4 -> n2 -> n1 -> n3 visits D, B, A, C in that order, and
+        // reversing the endpoints must reverse the (non-commutative) result.
+        assert_eq!(hld.path_query(4, 3, &segment_tree), "DBAC");
+        assert_eq!(hld.path_query(3, 4, &segment_tree), "CABD");
+    }
+
+    #[test]
+    fn range_query_preserves_left_to_right_order_for_a_non_commutative_combine() {
+        // String concatenation is associative but not commutative, so this
+        // only passes if `range_query` folds `[lo, hi]` left-to-right.
+        let letters = vec!["a".to_string(), "b".to_string(), "c".to_string(), "d".to_string()];
+        let segment_tree = SegmentTree::build(
+            letters,
+            |a: &String, b: &String| format!("{a}{b}"),
+            String::new(),
+        );
+
+        assert_eq!(segment_tree.range_query(0, 3), "abcd");
+        assert_eq!(segment_tree.range_query(1, 2), "bc");
+    }
+
+    #[test]
+    fn has_euler_path_accepts_a_simple_path() {
+        let n3 = Rc::new(Node::new(3, vec![]));
+        let n2 = Rc::new(Node::new(2, vec![Edge::new(1, n3.clone())]));
+        let n1 = Rc::new(Node::new(1, vec![Edge::new(1, n2.clone())]));
+
+        let graph = Graph::new();
+
+        assert!(graph.is_connected(&n1));
+        assert!(graph.has_euler_path(&n1));
+    }
+
+    #[test]
+    fn has_euler_path_rejects_a_star_with_four_odd_vertices() {
+        let n2 = Rc::new(Node::new(2, vec![]));
+        let n3 = Rc::new(Node::new(3, vec![]));
+        let n4 = Rc::new(Node::new(4, vec![]));
+        let n1 = Rc::new(Node::new(
+            1,
+            vec![
+                Edge::new(1, n2.clone()),
+                Edge::new(1, n3.clone()),
+                Edge::new(1, n4.clone()),
+            ],
+        ));
+
+        let graph = Graph::new();
+
+        assert!(!graph.has_euler_path(&n1));
+    }
+
+    #[test]
+    fn is_connected_ignores_isolated_vertices_but_rejects_real_disconnection() {
+        // Vertex 2 is isolated and must be ignored; node 0 and node 1 are
+        // joined by an edge, so the graph is connected once isolation is
+        // ignored.
+        let connected = Graph::from_adjacency_matrix("0 1 0\n0 0 0\n0 0 0").unwrap();
+        assert!(connected.is_connected(&connected.nodes()[0]));
+        assert!(connected.has_euler_path(&connected.nodes()[0]));
+
+        // Two disjoint edges: neither vertex is isolated, and node 2 is
+        // genuinely unreachable from node 0.
+        let disconnected =
+            Graph::from_adjacency_matrix("0 1 0 0\n0 0 0 0\n0 0 0 1\n0 0 0 0").unwrap();
+        assert!(!disconnected.is_connected(&disconnected.nodes()[0]));
+        assert!(!disconnected.has_euler_path(&disconnected.nodes()[0]));
+    }
+
+    #[test]
+    fn adjacency_matrix_round_trips_through_a_weighted_graph() {
+        // 0 -> 1 (weight 2), 0 -> 2 (weight 3), 1 -> 2 (weight 1).
+        let matrix = "0 2 3\n0 0 1\n0 0 0";
+
+        let graph = Graph::from_weighted_adjacency_matrix(matrix).unwrap();
+
+        assert_eq!(graph.nodes().len(), 3);
+        assert_eq!(graph.to_adjacency_matrix(), matrix);
+
+        let dist = graph.dijkstra(&graph.nodes()[0], None);
+        assert_eq!(dist[&2], 3);
+    }
+
+    #[test]
+    fn unweighted_adjacency_matrix_collapses_nonzero_cells_to_weight_one() {
+        let graph = Graph::from_adjacency_matrix("0 5\n0 0").unwrap();
+
+        assert_eq!(graph.nodes()[0].children_edges_iter().next().unwrap().weight, 1);
+    }
+
+    #[test]
+    fn adjacency_matrix_rejects_a_cycle() {
+        // 0 -> 1 -> 0 can't be represented: `Node`'s children are fixed at
+        // construction, so no node can point back at one that depends on it.
+        let result = Graph::from_adjacency_matrix("0 1\n1 0");
+        match result {
+            Err(err) => assert!(err.contains("cycle")),
+            Ok(_) => panic!("expected a cycle error"),
+        }
+    }
+
+    #[test]
+    fn reachability_finds_indirect_paths_but_not_unreachable_nodes() {
+        // 0 -> 1 -> 2, plus an isolated node 3.
+        let graph =
+            Graph::from_adjacency_matrix("0 1 0 0\n0 0 1 0\n0 0 0 0\n0 0 0 0").unwrap();
+        let reachability = graph.reachability();
+
+        assert!(reachability.can_reach(0, 2));
+        assert!(reachability.can_reach(0, 1));
+        assert!(!reachability.can_reach(2, 0));
+        assert!(!reachability.can_reach(0, 3));
+        assert!(!reachability.can_reach(3, 0));
+    }
 }